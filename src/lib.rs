@@ -129,21 +129,29 @@
 
 extern crate chrono;
 extern crate log;
+extern crate regex;
 extern crate termcolor;
 extern crate thread_local;
 
-use chrono::Local;
+use chrono::{Local, Utc};
 use log::{LogLevel, LogLevelFilter, LogMetadata};
+use regex::Regex;
 use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
 use std::fmt;
-use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use termcolor::{Color, ColorSpec, NoColor, StandardStream, WriteColor};
 use thread_local::CachedThreadLocal;
 
+/// Signature of a user-supplied line formatter, see [`StdErrLog::format`]
+type FormatFn = dyn Fn(&mut dyn Write, &log::LogRecord) -> io::Result<()> + Send + Sync;
+
 pub use termcolor::ColorChoice;
 
 /// State of the timestampping in the logger.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Timestamp {
     /// Disable timestamping of log messages
     Off,
@@ -153,6 +161,35 @@ pub enum Timestamp {
     Microsecond,
     /// Timestamp with nanosecond granularity
     Nanosecond,
+    /// Timestamp using a custom `strftime`-style format string
+    Custom(String),
+}
+
+/// Destination for the log output
+#[derive(Clone, Debug, Default)]
+pub enum Output {
+    /// Write to stderr, the default
+    #[default]
+    Stderr,
+    /// Write to stdout
+    Stdout,
+    /// Write to a file at the given path, appending to it if it already
+    /// exists. Color is always disabled for this destination.
+    File(PathBuf),
+    /// Write to a file at the given path like `File`, but cap it at
+    /// `capacity` bytes, rotating it out to `path.1`, `path.2`, ... (up
+    /// to `num_rotations` of them, oldest discarded) once a record
+    /// would push it past that capacity.
+    RotatingFile(PathBuf, u64, usize),
+}
+
+/// The open file handle and byte counter backing `Output::RotatingFile`,
+/// shared (via a `Mutex`) across every thread and clone of a `StdErrLog`
+/// so concurrent writers rotate and count bytes against the same file
+/// instead of racing over independent per-thread handles.
+struct RotatingFileState {
+    file: File,
+    written: u64,
 }
 
 /// Data specific to this logger
@@ -160,9 +197,16 @@ pub struct StdErrLog {
     verbosity: LogLevelFilter,
     quiet: bool,
     timestamp: Timestamp,
+    utc: bool,
     modules: Vec<String>,
-    writer: CachedThreadLocal<RefCell<io::LineWriter<StandardStream>>>,
+    directives: Vec<(String, LogLevelFilter)>,
+    writer: CachedThreadLocal<RefCell<io::LineWriter<Box<dyn WriteColor + Send>>>>,
+    rotating: Arc<Mutex<Option<RotatingFileState>>>,
+    output: Output,
     color_choice: ColorChoice,
+    colors: [ColorSpec; 5],
+    format: Option<Arc<FormatFn>>,
+    filter: Option<Regex>,
 }
 
 impl fmt::Debug for StdErrLog {
@@ -171,9 +215,16 @@ impl fmt::Debug for StdErrLog {
             .field("verbosity", &self.verbosity)
             .field("quiet", &self.quiet)
             .field("timestamp", &self.timestamp)
+            .field("utc", &self.utc)
             .field("modules", &self.modules)
+            .field("directives", &self.directives)
             .field("writer", &"stderr")
+            .field("rotating", &"<mutex>")
+            .field("output", &self.output)
             .field("color_choice", &self.color_choice)
+            .field("colors", &self.colors)
+            .field("format", &self.format.as_ref().map(|_| "custom"))
+            .field("filter", &self.filter)
             .finish()
     }
 }
@@ -182,7 +233,14 @@ impl Clone for StdErrLog {
     fn clone(&self) -> StdErrLog {
         StdErrLog {
             modules: self.modules.clone(),
+            directives: self.directives.clone(),
             writer: CachedThreadLocal::new(),
+            timestamp: self.timestamp.clone(),
+            rotating: self.rotating.clone(),
+            output: self.output.clone(),
+            colors: self.colors.clone(),
+            format: self.format.clone(),
+            filter: self.filter.clone(),
             .. *self
         }
     }
@@ -190,7 +248,8 @@ impl Clone for StdErrLog {
 
 impl log::Log for StdErrLog {
     fn enabled(&self, metadata: &LogMetadata) -> bool {
-        metadata.level() <= self.log_level_filter() && self.includes_module(metadata.target())
+        !self.quiet && self.includes_module(metadata.target()) &&
+            metadata.level() <= self.module_level(metadata.target())
     }
 
     fn log(&self, record: &log::LogRecord) {
@@ -200,35 +259,53 @@ impl log::Log for StdErrLog {
             return;
         }
 
-        let writer =
-            self.writer.get_or(|| Box::new(RefCell::new(io::LineWriter::new(StandardStream::stderr(self.color_choice)))));
+        // skip records whose message doesn't match the configured filter
+        if let Some(ref filter) = self.filter {
+            if !filter.is_match(&format!("{}", record.args())) {
+                return;
+            }
+        }
+
+        // build the full line up front so its size is known before a single
+        // byte of it is written, keeping rotation from ever splitting a line
+        let mut line: Vec<u8> = Vec::new();
+        if let Some(ref format) = self.format {
+            let _ = format(&mut line, record);
+        } else {
+            let fmt = match self.timestamp {
+                Timestamp::Second => Some("%Y-%m-%dT%H:%M:%S%:z"),
+                Timestamp::Microsecond => Some("%Y-%m-%dT%H:%M:%S%.6f%:z"),
+                Timestamp::Nanosecond => Some("%Y-%m-%dT%H:%M:%S%.9f%:z"),
+                Timestamp::Custom(ref fmt) => Some(fmt.as_str()),
+                Timestamp::Off => None,
+            };
+            if let Some(fmt) = fmt {
+                if self.utc {
+                    let _ = write!(line, "{} - ", Utc::now().format(fmt));
+                } else {
+                    let _ = write!(line, "{} - ", Local::now().format(fmt));
+                }
+            }
+            let _ = writeln!(line, "{} - {}", record.level(), record.args());
+        }
+
+        // rotating files are written through a mutex-guarded handle shared
+        // by every thread and clone, rather than the per-thread `writer`
+        // below, since rotation has to serialize across all writers to
+        // the same path instead of racing over independent file handles
+        if let Output::RotatingFile(ref path, capacity, num_rotations) = self.output {
+            self.write_rotating(path, capacity, num_rotations, &line);
+            return;
+        }
+
+        let writer = self.writer.get_or(|| Box::new(RefCell::new(io::LineWriter::new(self.open_writer()))));
         let mut writer = writer.borrow_mut();
-        let color = match record.metadata().level() {
-            LogLevel::Error => Color::Red,
-            LogLevel::Warn => Color::Magenta,
-            LogLevel::Info => Color::Yellow,
-            LogLevel::Debug => Color::Cyan,
-            LogLevel::Trace => Color::Blue,
-        };
+
+        let color = &self.colors[record.metadata().level() as usize - 1];
         {
-            writer.get_mut().set_color(ColorSpec::new().set_fg(Some(color))).expect("failed to set color");
+            writer.get_mut().set_color(color).expect("failed to set color");
         }
-        match self.timestamp {
-            Timestamp::Second => {
-                let fmt = "%Y-%m-%dT%H:%M:%S%:z";
-                let _ = write!(writer, "{} - ", Local::now().format(fmt));
-            },
-            Timestamp::Microsecond => {
-                let fmt = "%Y-%m-%dT%H:%M:%S%.6f%:z";
-                let _ = write!(writer, "{} - ", Local::now().format(fmt));
-            },
-            Timestamp::Nanosecond => {
-                let fmt = "%Y-%m-%dT%H:%M:%S%.9f%:z";
-                let _ = write!(writer, "{} - ", Local::now().format(fmt));
-            },
-            Timestamp::Off => {},
-        }
-        let _ = writeln!(writer, "{} - {}", record.level(), record.args());
+        let _ = writer.write_all(&line);
         {
             writer.get_mut().reset().expect("failed to reset the color");
         }
@@ -236,15 +313,77 @@ impl log::Log for StdErrLog {
 }
 
 impl StdErrLog {
+    /// opens the writer for the currently configured `output`, to be
+    /// cached in the per-thread `writer` slot
+    fn open_writer(&self) -> Box<dyn WriteColor + Send> {
+        match self.output {
+            Output::Stderr => Box::new(StandardStream::stderr(self.color_choice)),
+            Output::Stdout => Box::new(StandardStream::stdout(self.color_choice)),
+            Output::File(ref path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .expect("unable to open log file");
+                Box::new(NoColor::new(file))
+            }
+            Output::RotatingFile(..) => {
+                unreachable!("RotatingFile is written through write_rotating(), not the per-thread writer")
+            }
+        }
+    }
+
+    /// Writes `line` to the rotating file at `path`, rotating it out first
+    /// if writing `line` would push it past `capacity` bytes. The file
+    /// handle and byte counter live behind `self.rotating`'s mutex rather
+    /// than the per-thread `writer`, so every thread and clone of this
+    /// logger serializes its writes and rotation decisions against the
+    /// same file instead of racing over independent handles.
+    fn write_rotating(&self, path: &Path, capacity: u64, num_rotations: usize, line: &[u8]) {
+        let mut state = self.rotating.lock().expect("rotating file mutex poisoned");
+        if state.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .expect("unable to open log file");
+            let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+            *state = Some(RotatingFileState { file, written });
+        }
+
+        let state = state.as_mut().unwrap();
+        if state.written + line.len() as u64 > capacity {
+            let _ = state.file.flush();
+            rotate_file(path, num_rotations);
+            state.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .expect("unable to open log file");
+            state.written = 0;
+        }
+
+        if state.file.write_all(line).is_ok() {
+            state.written += line.len() as u64;
+        }
+    }
+
     /// creates a new stderr logger
     pub fn new() -> StdErrLog {
         StdErrLog {
             verbosity: LogLevelFilter::Error,
             quiet: false,
             timestamp: Timestamp::Off,
+            utc: false,
             modules: Vec::new(),
+            directives: Vec::new(),
             writer: CachedThreadLocal::new(),
+            rotating: Arc::new(Mutex::new(None)),
+            output: Output::Stderr,
             color_choice: ColorChoice::Auto,
+            colors: default_colors(),
+            format: None,
+            filter: None,
         }
     }
 
@@ -274,12 +413,62 @@ impl StdErrLog {
         self
     }
 
+    /// Use UTC rather than the local time zone for timestamps
+    pub fn utc(&mut self, utc: bool) -> &mut StdErrLog {
+        self.utc = utc;
+        self
+    }
+
     /// Enables or disables the use of color in log messages
     pub fn color(&mut self, choice: ColorChoice) -> &mut StdErrLog {
         self.color_choice = choice;
         self
     }
 
+    /// Sets where log messages are written to, stderr by default
+    pub fn output(&mut self, output: Output) -> &mut StdErrLog {
+        self.output = output;
+        self
+    }
+
+    /// Sets the `ColorSpec` used to render messages at `level`, letting
+    /// callers theme individual levels (e.g. bold, intense, or
+    /// reversed colors for errors) instead of the built-in palette
+    pub fn level_color(&mut self, level: LogLevel, spec: ColorSpec) -> &mut StdErrLog {
+        self.colors[level as usize - 1] = spec;
+        self
+    }
+
+    /// Sets a custom line formatter, replacing the built-in
+    /// `"{level} - {args}"` layout (and any configured timestamp
+    /// prefix) entirely. Color is still applied around the written
+    /// line as usual.
+    pub fn format<F>(&mut self, format: F) -> &mut StdErrLog
+        where F: Fn(&mut dyn Write, &log::LogRecord) -> io::Result<()> + Send + Sync + 'static
+    {
+        self.format = Some(Arc::new(format));
+        self
+    }
+
+    /// Writes to a file at `path`, rotating it out to `path.1`, `path.2`,
+    /// ... once it would grow past `capacity_bytes`, keeping at most
+    /// `num_rotations` of those
+    pub fn rotating_file<P: Into<PathBuf>>(&mut self,
+                                            path: P,
+                                            capacity_bytes: u64,
+                                            num_rotations: usize)
+                                            -> &mut StdErrLog {
+        self.output = Output::RotatingFile(path.into(), capacity_bytes, num_rotations);
+        self
+    }
+
+    /// Only logs messages whose formatted text matches `filter`,
+    /// composing with the existing module and level filtering
+    pub fn filter(&mut self, filter: Regex) -> &mut StdErrLog {
+        self.filter = Some(filter);
+        self
+    }
+
     /// specify a module to allow to log to stderr
     pub fn module<T: Into<String>>(&mut self, module: T) -> &mut StdErrLog {
         self._module(module.into())
@@ -314,14 +503,87 @@ impl StdErrLog {
         self
     }
 
+    /// Adds a directive setting the log level for a specific module, or,
+    /// if `module` is `None`, the default level used for modules
+    /// without a directive of their own.
+    pub fn add_directive<T: Into<String>>(&mut self,
+                                           module: Option<T>,
+                                           level: LogLevelFilter)
+                                           -> &mut StdErrLog {
+        match module {
+            Some(module) => self._add_directive(module.into(), level),
+            None => {
+                self.verbosity = level;
+                self
+            }
+        }
+    }
+
+    fn _add_directive(&mut self, module: String, level: LogLevelFilter) -> &mut StdErrLog {
+        match self.directives.binary_search_by(|(m, _)| m.as_str().cmp(module.as_str())) {
+            Ok(i) => self.directives[i] = (module, level),
+            Err(i) => self.directives.insert(i, (module, level)),
+        }
+        self
+    }
+
+    /// Parses a `RUST_LOG`-style filter string, e.g.
+    /// `"mycrate=debug,mycrate::net=trace,hyper=warn"` or a bare
+    /// `"debug"` to set the default level for modules without their
+    /// own directive.
+    pub fn parse_filters(&mut self, filters: &str) -> &mut StdErrLog {
+        for directive in filters.split(',') {
+            let directive = directive.trim();
+            if directive.is_empty() {
+                continue;
+            }
+            let mut parts = directive.splitn(2, '=');
+            let module_or_level = parts.next().unwrap();
+            match parts.next() {
+                Some(level) => {
+                    if let Some(level) = parse_log_level_filter(level) {
+                        self.add_directive(Some(module_or_level), level);
+                    }
+                }
+                None => {
+                    if let Some(level) = parse_log_level_filter(module_or_level) {
+                        self.add_directive(None::<String>, level);
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// The global level the `log` crate's `max_log_level()` gate should
+    /// be set to: at least as permissive as the default `verbosity` and
+    /// every per-module directive, since those directives can only ever
+    /// fire for records that get past that gate in the first place.
     fn log_level_filter(&self) -> LogLevelFilter {
         if self.quiet {
             LogLevelFilter::Off
         } else {
-            self.verbosity
+            self.directives
+                .iter()
+                .fold(self.verbosity, |max, &(_, level)| if level > max { level } else { max })
         }
     }
 
+    /// Looks up the log level in effect for `module_path`, finding the
+    /// longest matching directive, and falling back to the default
+    /// `verbosity` when there's no match. Directives aren't kept
+    /// prefix-free the way `self.modules` is, so every directive that's
+    /// an ancestor of `module_path` is considered, not just the nearest
+    /// lexicographic predecessor.
+    fn module_level(&self, module_path: &str) -> LogLevelFilter {
+        self.directives
+            .iter()
+            .filter(|&(module, _)| is_submodule(module, module_path))
+            .max_by_key(|&(module, _)| module.len())
+            .map(|&(_, level)| level)
+            .unwrap_or(self.verbosity)
+    }
+
     fn includes_module(&self, module_path: &str) -> bool {
         // If modules is empty, include all module paths
         if self.modules.is_empty() {
@@ -366,6 +628,51 @@ pub fn new() -> StdErrLog {
     StdErrLog::new()
 }
 
+/// the default per-level colors, indexed by `LogLevel as usize - 1`
+fn default_colors() -> [ColorSpec; 5] {
+    let mut colors = [ColorSpec::new(), ColorSpec::new(), ColorSpec::new(), ColorSpec::new(), ColorSpec::new()];
+    colors[LogLevel::Error as usize - 1].set_fg(Some(Color::Red));
+    colors[LogLevel::Warn as usize - 1].set_fg(Some(Color::Magenta));
+    colors[LogLevel::Info as usize - 1].set_fg(Some(Color::Yellow));
+    colors[LogLevel::Debug as usize - 1].set_fg(Some(Color::Cyan));
+    colors[LogLevel::Trace as usize - 1].set_fg(Some(Color::Blue));
+    colors
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// shifts `path.1` -> `path.2` -> ... -> `path.num_rotations` (discarding
+/// the oldest) and then moves `path` itself to `path.1`
+fn rotate_file(path: &Path, num_rotations: usize) {
+    if num_rotations == 0 {
+        let _ = fs::remove_file(path);
+        return;
+    }
+    for n in (1..num_rotations).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            let _ = fs::rename(from, rotated_path(path, n + 1));
+        }
+    }
+    let _ = fs::rename(path, rotated_path(path, 1));
+}
+
+fn parse_log_level_filter(level: &str) -> Option<LogLevelFilter> {
+    match level.trim().to_lowercase().as_str() {
+        "off" => Some(LogLevelFilter::Off),
+        "error" => Some(LogLevelFilter::Error),
+        "warn" => Some(LogLevelFilter::Warn),
+        "info" => Some(LogLevelFilter::Info),
+        "debug" => Some(LogLevelFilter::Debug),
+        "trace" => Some(LogLevelFilter::Trace),
+        _ => None,
+    }
+}
+
 fn is_submodule(parent: &str, possible_child: &str) -> bool {
     // Treat as bytes, because we'll be doing slicing, and we only care about ':' chars
     let parent = parent.as_bytes();
@@ -391,6 +698,145 @@ fn is_submodule(parent: &str, possible_child: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::is_submodule;
+    use log::LogLevelFilter;
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn open_writer_file_creates_and_appends() {
+        let mut path = std::env::temp_dir();
+        path.push("stderrlog_test_open_writer_file.log");
+        let _ = fs::remove_file(&path);
+
+        let mut log = super::new();
+        log.output(super::Output::File(path.clone()));
+
+        log.open_writer().write_all(b"hello\n").unwrap();
+        // opening again should append to the existing file, not truncate it
+        log.open_writer().write_all(b"world\n").unwrap();
+
+        assert_eq!("hello\nworld\n", fs::read_to_string(&path).unwrap());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn rotated_path_appends_rotation_number() {
+        let path = Path::new("/tmp/app.log");
+        assert_eq!(PathBuf::from("/tmp/app.log.1"), super::rotated_path(path, 1));
+        assert_eq!(PathBuf::from("/tmp/app.log.3"), super::rotated_path(path, 3));
+    }
+
+    #[test]
+    fn rotate_file_shifts_existing_rotations_and_discards_oldest() {
+        let dir = std::env::temp_dir().join("stderrlog_test_rotate_file");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("app.log");
+
+        fs::write(&base, b"current").unwrap();
+        fs::write(super::rotated_path(&base, 1), b"rotated-1").unwrap();
+        fs::write(super::rotated_path(&base, 2), b"rotated-2").unwrap();
+
+        super::rotate_file(&base, 2);
+
+        assert!(!base.exists());
+        assert_eq!(b"current".to_vec(), fs::read(super::rotated_path(&base, 1)).unwrap());
+        assert_eq!(b"rotated-1".to_vec(), fs::read(super::rotated_path(&base, 2)).unwrap());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_rotating_rotates_out_once_capacity_exceeded() {
+        let dir = std::env::temp_dir().join("stderrlog_test_write_rotating");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+
+        let log = super::new();
+        // each line is 5 bytes; a capacity of 10 allows exactly two of
+        // them in a file before the third has to push a rotation
+        log.write_rotating(&path, 10, 2, b"11111");
+        log.write_rotating(&path, 10, 2, b"22222");
+        log.write_rotating(&path, 10, 2, b"33333");
+
+        // the first two lines filled the file to exactly its 10-byte
+        // capacity without splitting, so the third pushed it out whole
+        assert_eq!(b"1111122222".to_vec(), fs::read(super::rotated_path(&path, 1)).unwrap());
+        assert_eq!(b"33333".to_vec(), fs::read(&path).unwrap());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotate_file_with_zero_rotations_deletes_instead_of_keeping_any() {
+        let dir = std::env::temp_dir().join("stderrlog_test_rotate_file_zero");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("app.log");
+        fs::write(&base, b"current").unwrap();
+
+        super::rotate_file(&base, 0);
+
+        assert!(!base.exists());
+        assert!(!super::rotated_path(&base, 1).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn format_stores_custom_callback() {
+        let mut log = super::new();
+        assert!(log.format.is_none());
+
+        log.format(|buf, record| write!(buf, "{}", record.args()));
+
+        assert!(log.format.is_some());
+    }
+
+    #[test]
+    fn level_color_overrides_default_for_that_level_only() {
+        use log::LogLevel;
+        use termcolor::{Color, ColorSpec};
+
+        let mut log = super::new();
+        let mut spec = ColorSpec::new();
+        spec.set_fg(Some(Color::Green));
+
+        log.level_color(LogLevel::Info, spec.clone());
+
+        assert_eq!(spec, log.colors[LogLevel::Info as usize - 1]);
+        assert_ne!(spec, log.colors[LogLevel::Error as usize - 1]);
+    }
+
+    #[test]
+    fn timestamp_and_utc_setters_store_configuration() {
+        let mut log = super::new();
+        log.timestamp(super::Timestamp::Custom("%Y".to_string()));
+        log.utc(true);
+
+        match log.timestamp {
+            super::Timestamp::Custom(ref fmt) => assert_eq!("%Y", fmt),
+            _ => panic!("expected a custom timestamp format"),
+        }
+        assert!(log.utc);
+    }
+
+    #[test]
+    fn filter_matches_only_the_configured_pattern() {
+        use regex::Regex;
+
+        let mut log = super::new();
+        assert!(log.filter.is_none());
+
+        log.filter(Regex::new("^db::").unwrap());
+
+        let filter = log.filter.as_ref().unwrap();
+        assert!(filter.is_match("db::query failed"));
+        assert!(!filter.is_match("http::request failed"));
+    }
 
     #[test]
     fn submodule() {
@@ -405,11 +851,54 @@ mod tests {
     }
 
     #[test]
-    fn test_default_level() {
+    fn log_level_filter_defaults_to_verbosity_with_no_directives() {
+        // with no directives configured, the global gate computed from
+        // `log_level_filter()` should stay at the default `verbosity`
+        // (Error) rather than being raised by anything
+        assert_eq!(LogLevelFilter::Error, super::new().log_level_filter());
+    }
+
+    #[test]
+    fn directive_raises_global_max_log_level() {
         extern crate log;
 
-        super::new().module(module_path!()).init().unwrap();
+        // default verbosity stays at Error, but a directive asks one
+        // module for Trace; the global gate has to follow or `log!`
+        // calls in that module would never reach `enabled()`/`log()`
+        super::new().parse_filters("some::module=trace").init().unwrap();
+
+        assert_eq!(log::LogLevel::Trace, log::max_log_level())
+    }
+
+    #[test]
+    fn module_level_directives() {
+        let mut log = super::new();
+        log.parse_filters("debug,hyper=warn,hyper::net=trace");
+
+        assert_eq!(LogLevelFilter::Debug, log.module_level("myapp"));
+        assert_eq!(LogLevelFilter::Warn, log.module_level("hyper"));
+        assert_eq!(LogLevelFilter::Warn, log.module_level("hyper::client"));
+        assert_eq!(LogLevelFilter::Trace, log.module_level("hyper::net"));
+        assert_eq!(LogLevelFilter::Trace, log.module_level("hyper::net::tcp"));
+    }
+
+    #[test]
+    fn module_level_ignores_non_ancestor_sibling_directive() {
+        let mut log = super::new();
+        log.add_directive(Some("a"), LogLevelFilter::Trace);
+        log.add_directive(Some("a0"), LogLevelFilter::Warn);
+
+        // "a0" sorts between "a" and "a::z::sub" but isn't its ancestor;
+        // "a" is, and must still be found even though it's not the
+        // nearest lexicographic predecessor
+        assert_eq!(LogLevelFilter::Trace, log.module_level("a::z::sub"));
+    }
+
+    #[test]
+    fn add_directive_without_module_sets_default() {
+        let mut log = super::new();
+        log.add_directive(None::<String>, LogLevelFilter::Trace);
 
-        assert_eq!(log::LogLevel::Error, log::max_log_level())
+        assert_eq!(LogLevelFilter::Trace, log.module_level("anything"));
     }
 }